@@ -0,0 +1,237 @@
+//! Embedded HTTP API for observability: exposes the same market/arb state the
+//! 60-second heartbeat logs, so a monitoring stack can scrape it instead of
+//! tailing logs.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::candles::{CandleAggregator, Resolution};
+use crate::execution::{size_arb, Leg};
+use crate::types::GlobalState;
+
+/// Shared handles every route needs. `GlobalState` alone used to be enough;
+/// candle history lives in its own aggregator so it can be indexed
+/// independently of market prices.
+#[derive(Clone)]
+struct ApiState {
+    state: Arc<GlobalState>,
+    candles: Arc<CandleAggregator>,
+}
+
+/// Bind address for the API server, configurable via env.
+fn bind_addr() -> SocketAddr {
+    std::env::var("API_BIND_ADDR")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 8080)))
+}
+
+#[derive(Serialize)]
+struct MarketView {
+    market_id: u16,
+    description: String,
+    kalshi_yes_cents: u16,
+    kalshi_no_cents: u16,
+    poly_yes_cents: u16,
+    poly_no_cents: u16,
+    has_kalshi: bool,
+    has_poly: bool,
+}
+
+#[derive(Serialize)]
+struct OpportunityView {
+    market_id: u16,
+    description: String,
+    cost_cents: u16,
+    executable_size: u32,
+    gap_cents: i16,
+    profit_cents: i16,
+}
+
+/// Flat row shape modeled on the CoinGecko `/tickers` endpoint, so existing
+/// dashboards built against that shape work against this bot unmodified.
+#[derive(Serialize)]
+struct TickerRow {
+    base: String,
+    target: String,
+    last: f64,
+    bid: f64,
+    ask: f64,
+    volume: f64,
+}
+
+async fn get_markets(State(api): State<ApiState>) -> Json<Vec<MarketView>> {
+    let state = &api.state;
+    let views = state
+        .markets
+        .iter()
+        .map(|market| {
+            let k_yes = market.kalshi_yes.best_price();
+            let k_no = market.kalshi_no.best_price();
+            let p_yes = market.poly_yes.best_price();
+            let p_no = market.poly_no.best_price();
+            MarketView {
+                market_id: market.market_id,
+                description: market
+                    .pair
+                    .as_ref()
+                    .map(|p| p.description.clone())
+                    .unwrap_or_default(),
+                kalshi_yes_cents: k_yes,
+                kalshi_no_cents: k_no,
+                poly_yes_cents: p_yes,
+                poly_no_cents: p_no,
+                has_kalshi: k_yes > 0 && k_no > 0,
+                has_poly: p_yes > 0 && p_no > 0,
+            }
+        })
+        .collect();
+    Json(views)
+}
+
+/// Mirrors `ExecutionEngine::evaluate`'s sizing exactly, so a monitoring
+/// consumer of this endpoint sees the same size-limited blended cost the
+/// execution engine actually gates on, not a top-of-book number it would
+/// never get to execute at.
+async fn get_opportunities(State(api): State<ApiState>) -> Json<Vec<OpportunityView>> {
+    let state = &api.state;
+    let mut views = Vec::new();
+    for market in state.markets.iter() {
+        let (k_yes_levels, k_yes_depth) = market.kalshi_yes.levels();
+        let (k_no_levels, k_no_depth) = market.kalshi_no.levels();
+        let (p_yes_levels, p_yes_depth) = market.poly_yes.levels();
+        let (p_no_levels, p_no_depth) = market.poly_no.levels();
+
+        if k_yes_depth == 0 || k_no_depth == 0 {
+            continue;
+        }
+        let has_poly = p_yes_depth > 0 && p_no_depth > 0;
+
+        let sized = if has_poly {
+            let leg1 = size_arb(
+                Leg { levels: &p_yes_levels[..p_yes_depth], has_kalshi_fee: false },
+                Leg { levels: &k_no_levels[..k_no_depth], has_kalshi_fee: true },
+            );
+            let leg2 = size_arb(
+                Leg { levels: &k_yes_levels[..k_yes_depth], has_kalshi_fee: true },
+                Leg { levels: &p_no_levels[..p_no_depth], has_kalshi_fee: false },
+            );
+            match (leg1, leg2) {
+                (Some(a), Some(b)) => Some(if a.blended_cost_cents <= b.blended_cost_cents { a } else { b }),
+                (a, b) => a.or(b),
+            }
+        } else {
+            size_arb(
+                Leg { levels: &k_yes_levels[..k_yes_depth], has_kalshi_fee: true },
+                Leg { levels: &k_no_levels[..k_no_depth], has_kalshi_fee: true },
+            )
+        };
+
+        let Some(sized) = sized else {
+            continue;
+        };
+
+        views.push(OpportunityView {
+            market_id: market.market_id,
+            description: market
+                .pair
+                .as_ref()
+                .map(|p| p.description.clone())
+                .unwrap_or_default(),
+            cost_cents: sized.blended_cost_cents,
+            executable_size: sized.executable_size,
+            gap_cents: sized.blended_cost_cents as i16 - 100,
+            profit_cents: sized.profit_cents as i16,
+        });
+    }
+    views.sort_by_key(|v| v.cost_cents);
+    Json(views)
+}
+
+async fn get_tickers(State(api): State<ApiState>) -> Json<Vec<TickerRow>> {
+    let state = &api.state;
+    let mut rows = Vec::new();
+    for market in state.markets.iter() {
+        let Some(pair) = &market.pair else { continue };
+        let k_yes = market.kalshi_yes.best_price();
+        let k_no = market.kalshi_no.best_price();
+        if k_yes == 0 && k_no == 0 {
+            continue;
+        }
+        rows.push(TickerRow {
+            base: pair.kalshi_market_ticker.clone(),
+            target: "USD".to_string(),
+            last: k_yes as f64 / 100.0,
+            bid: if k_no > 0 { (100 - k_no as i32).max(0) as f64 / 100.0 } else { 0.0 },
+            ask: k_yes as f64 / 100.0,
+            volume: 0.0,
+        });
+    }
+    Json(rows)
+}
+
+#[derive(Deserialize)]
+struct CandlesQuery {
+    market_id: u16,
+    #[serde(default)]
+    resolution: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CandleView {
+    bucket_start: u64,
+    open: i16,
+    high: i16,
+    low: i16,
+    close: i16,
+    samples: u32,
+}
+
+async fn get_candles(State(api): State<ApiState>, Query(query): Query<CandlesQuery>) -> Json<Vec<CandleView>> {
+    let resolution = match query.resolution.as_deref() {
+        Some("5m") => Resolution::FiveMin,
+        Some("1h") => Resolution::OneHour,
+        _ => Resolution::OneMin,
+    };
+    let views = api
+        .candles
+        .sealed_candles(query.market_id, resolution)
+        .into_iter()
+        .map(|c| CandleView {
+            bucket_start: c.bucket_start,
+            open: c.open,
+            high: c.high,
+            low: c.low,
+            close: c.close,
+            samples: c.samples,
+        })
+        .collect();
+    Json(views)
+}
+
+/// Builds the router. Kept separate from `serve` so tests (if any are added
+/// later) can exercise routes without binding a socket.
+fn router(api: ApiState) -> Router {
+    Router::new()
+        .route("/markets", get(get_markets))
+        .route("/opportunities", get(get_opportunities))
+        .route("/tickers", get(get_tickers))
+        .route("/candles", get(get_candles))
+        .with_state(api)
+}
+
+/// Serves the API until the process exits. Spawned as its own tokio task
+/// alongside the WS loops and heartbeat.
+pub async fn serve(state: Arc<GlobalState>, candles: Arc<CandleAggregator>) -> anyhow::Result<()> {
+    let addr = bind_addr();
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!("🌐 HTTP API listening on {}", addr);
+    axum::serve(listener, router(ApiState { state, candles })).await?;
+    Ok(())
+}
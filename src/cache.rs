@@ -0,0 +1,41 @@
+//! On-disk cache mapping league team names to the short codes Kalshi uses in
+//! its market tickers (e.g. "Lakers" -> "LAL").
+
+use std::collections::HashMap;
+use std::fs;
+
+use tracing::warn;
+
+const CACHE_PATH: &str = "team_cache.json";
+
+/// Team name -> Kalshi ticker code mapping, loaded once at startup.
+pub struct TeamCache {
+    codes: HashMap<String, String>,
+}
+
+impl TeamCache {
+    /// Loads the cache from disk, falling back to an empty cache if the file
+    /// is missing or malformed.
+    pub fn load() -> Self {
+        let codes = fs::read_to_string(CACHE_PATH)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_else(|| {
+                warn!("no team cache found at {}, starting empty", CACHE_PATH);
+                HashMap::new()
+            });
+        Self { codes }
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    pub fn code_for(&self, team: &str) -> Option<&str> {
+        self.codes.get(team).map(|s| s.as_str())
+    }
+}
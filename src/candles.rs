@@ -0,0 +1,248 @@
+//! Rolling OHLC aggregation of the implied arbitrage gap (`cost - threshold`,
+//! in cents) per market pair, so a user can see historically when a pair
+//! crosses into profitable territory instead of only the instantaneous
+//! heartbeat reading.
+//!
+//! Updates are integer-only and allocate nothing per tick: each market gets a
+//! fixed-size ring buffer per resolution, sized up front from the market
+//! count known at startup.
+
+use std::sync::Mutex;
+
+/// How finely to bucket candles. Kept small and explicit rather than a
+/// generic duration since these are the only resolutions the API/storage
+/// sink need to expose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    OneMin,
+    FiveMin,
+    OneHour,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution::OneMin, Resolution::FiveMin, Resolution::OneHour];
+
+    pub fn period_secs(self) -> u64 {
+        match self {
+            Resolution::OneMin => 60,
+            Resolution::FiveMin => 300,
+            Resolution::OneHour => 3600,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolution::OneMin => "1m",
+            Resolution::FiveMin => "5m",
+            Resolution::OneHour => "1h",
+        }
+    }
+}
+
+/// One sealed (or in-progress) bucket. The gap is small enough in magnitude
+/// that `i16` cents is always sufficient.
+#[derive(Debug, Clone, Copy)]
+pub struct Candle {
+    pub bucket_start: u64,
+    pub open: i16,
+    pub high: i16,
+    pub low: i16,
+    pub close: i16,
+    pub samples: u32,
+}
+
+/// How many sealed candles to retain per market per resolution before the
+/// oldest is overwritten. 720 x 1m = 12h, 720 x 1h = 30 days.
+const RING_CAPACITY: usize = 720;
+
+struct Ring {
+    buf: Vec<Candle>,
+    next: usize,
+    filled: bool,
+    current: Option<Candle>,
+}
+
+impl Ring {
+    fn new() -> Self {
+        Self {
+            buf: Vec::with_capacity(RING_CAPACITY),
+            next: 0,
+            filled: false,
+            current: None,
+        }
+    }
+
+    /// Updates the in-progress bucket for `now_secs`, sealing and returning
+    /// the previous bucket if `now_secs` rolled into a new one.
+    fn update(&mut self, gap_cents: i16, now_secs: u64, period_secs: u64) -> Option<Candle> {
+        let bucket_start = now_secs - (now_secs % period_secs);
+        match &mut self.current {
+            Some(candle) if candle.bucket_start == bucket_start => {
+                candle.high = candle.high.max(gap_cents);
+                candle.low = candle.low.min(gap_cents);
+                candle.close = gap_cents;
+                candle.samples += 1;
+                None
+            }
+            Some(candle) => {
+                let sealed = *candle;
+                self.seal(sealed);
+                self.current = Some(Candle {
+                    bucket_start,
+                    open: gap_cents,
+                    high: gap_cents,
+                    low: gap_cents,
+                    close: gap_cents,
+                    samples: 1,
+                });
+                Some(sealed)
+            }
+            None => {
+                self.current = Some(Candle {
+                    bucket_start,
+                    open: gap_cents,
+                    high: gap_cents,
+                    low: gap_cents,
+                    close: gap_cents,
+                    samples: 1,
+                });
+                None
+            }
+        }
+    }
+
+    fn seal(&mut self, candle: Candle) {
+        if self.buf.len() < RING_CAPACITY {
+            self.buf.push(candle);
+        } else {
+            self.buf[self.next] = candle;
+            self.filled = true;
+        }
+        self.next = (self.next + 1) % RING_CAPACITY;
+    }
+
+    /// Sealed candles in chronological order, oldest first.
+    fn sealed(&self) -> Vec<Candle> {
+        if !self.filled {
+            self.buf.clone()
+        } else {
+            let mut out = Vec::with_capacity(RING_CAPACITY);
+            out.extend_from_slice(&self.buf[self.next..]);
+            out.extend_from_slice(&self.buf[..self.next]);
+            out
+        }
+    }
+}
+
+struct MarketCandles {
+    one_min: Mutex<Ring>,
+    five_min: Mutex<Ring>,
+    one_hour: Mutex<Ring>,
+}
+
+impl MarketCandles {
+    fn new() -> Self {
+        Self {
+            one_min: Mutex::new(Ring::new()),
+            five_min: Mutex::new(Ring::new()),
+            one_hour: Mutex::new(Ring::new()),
+        }
+    }
+
+    fn ring(&self, resolution: Resolution) -> &Mutex<Ring> {
+        match resolution {
+            Resolution::OneMin => &self.one_min,
+            Resolution::FiveMin => &self.five_min,
+            Resolution::OneHour => &self.one_hour,
+        }
+    }
+}
+
+/// One `MarketCandles` per market, indexed by `market_id`. Sized once at
+/// startup from `GlobalState::market_count`.
+pub struct CandleAggregator {
+    per_market: Vec<MarketCandles>,
+}
+
+impl CandleAggregator {
+    pub fn new(market_count: usize) -> Self {
+        Self {
+            per_market: (0..market_count).map(|_| MarketCandles::new()).collect(),
+        }
+    }
+
+    /// Feeds one gap sample (in cents, can be negative) into every
+    /// resolution's current bucket for `market_id`. Returns any buckets that
+    /// sealed as a result, for callers that want to mirror them to storage.
+    pub fn record(&self, market_id: u16, gap_cents: i16, now_secs: u64) -> Vec<(Resolution, Candle)> {
+        let Some(mc) = self.per_market.get(market_id as usize) else {
+            return Vec::new();
+        };
+        let mut sealed = Vec::new();
+        for resolution in Resolution::ALL {
+            if let Some(candle) = mc.ring(resolution).lock().unwrap().update(gap_cents, now_secs, resolution.period_secs()) {
+                sealed.push((resolution, candle));
+            }
+        }
+        sealed
+    }
+
+    /// Sealed candles for a market/resolution, oldest first.
+    pub fn sealed_candles(&self, market_id: u16, resolution: Resolution) -> Vec<Candle> {
+        self.per_market
+            .get(market_id as usize)
+            .map(|mc| mc.ring(resolution).lock().unwrap().sealed())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollover_seals_previous_bucket_with_correct_ohlc() {
+        let agg = CandleAggregator::new(1);
+
+        assert!(agg.record(0, 10, 0).is_empty());
+        assert!(agg.record(0, 25, 30).is_empty());
+        assert!(agg.record(0, -5, 59).is_empty());
+
+        let sealed = agg.record(0, 3, 61);
+        assert_eq!(sealed.len(), 1);
+        let (resolution, candle) = sealed[0];
+        assert_eq!(resolution, Resolution::OneMin);
+        assert_eq!(candle.bucket_start, 0);
+        assert_eq!(candle.open, 10);
+        assert_eq!(candle.high, 25);
+        assert_eq!(candle.low, -5);
+        assert_eq!(candle.close, -5);
+        assert_eq!(candle.samples, 3);
+
+        let one_min = agg.sealed_candles(0, Resolution::OneMin);
+        assert_eq!(one_min.len(), 1);
+        assert_eq!(one_min[0].bucket_start, 0);
+    }
+
+    #[test]
+    fn ring_wraps_after_capacity_keeping_oldest_first_order() {
+        let agg = CandleAggregator::new(1);
+
+        // One sample per minute for RING_CAPACITY + 2 minutes seals
+        // RING_CAPACITY + 1 buckets, forcing the ring to wrap once.
+        for minute in 0..=(RING_CAPACITY as u64 + 1) {
+            agg.record(0, minute as i16, minute * 60);
+        }
+
+        let sealed = agg.sealed_candles(0, Resolution::OneMin);
+        assert_eq!(sealed.len(), RING_CAPACITY);
+        // The oldest surviving bucket is the second one ever opened (bucket 0
+        // was overwritten once the ring wrapped), and buckets stay in
+        // chronological order despite the underlying array wrapping.
+        assert_eq!(sealed[0].bucket_start, 60);
+        assert_eq!(sealed.last().unwrap().bucket_start, RING_CAPACITY as u64 * 60);
+        for pair in sealed.windows(2) {
+            assert!(pair[1].bucket_start > pair[0].bucket_start);
+        }
+    }
+}
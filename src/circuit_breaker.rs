@@ -0,0 +1,82 @@
+//! Trading circuit breaker: trips after repeated execution failures within a
+//! window and blocks further order placement until it cools down.
+
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Tunables for the circuit breaker, overridable via env so ops can tighten
+/// things in production without a recompile.
+#[derive(Debug, Clone, Copy)]
+pub struct CircuitBreakerConfig {
+    pub max_failures: u32,
+    pub window_secs: u64,
+    pub cooldown_secs: u64,
+}
+
+impl CircuitBreakerConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_failures: std::env::var("CB_MAX_FAILURES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            window_secs: std::env::var("CB_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            cooldown_secs: std::env::var("CB_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(300),
+        }
+    }
+}
+
+/// Trips open after `max_failures` execution failures inside `window_secs`,
+/// and stays open for `cooldown_secs` before allowing orders again.
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    failures: AtomicU32,
+    window_start: AtomicU64,
+    tripped_at: AtomicU64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            failures: AtomicU32::new(0),
+            window_start: AtomicU64::new(now_secs()),
+            tripped_at: AtomicU64::new(0),
+        }
+    }
+
+    /// True if the breaker is currently open (orders should be blocked).
+    pub fn is_open(&self) -> bool {
+        let tripped_at = self.tripped_at.load(Ordering::Acquire);
+        tripped_at != 0 && now_secs() - tripped_at < self.config.cooldown_secs
+    }
+
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Release);
+    }
+
+    pub fn record_failure(&self) {
+        let now = now_secs();
+        if now - self.window_start.load(Ordering::Acquire) > self.config.window_secs {
+            self.window_start.store(now, Ordering::Release);
+            self.failures.store(0, Ordering::Release);
+        }
+        let failures = self.failures.fetch_add(1, Ordering::AcqRel) + 1;
+        if failures >= self.config.max_failures {
+            self.tripped_at.store(now, Ordering::Release);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Compile-time configuration defaults, overridable at deploy time by a
+//! `markets.json`/`CONFIG_PATH` file so tuning thresholds or adding a league
+//! doesn't require a recompile.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tracing::{info, warn};
+
+/// Minimum combined-leg cost (as a fraction of $1) at or below which a pair is
+/// considered arbitrageable, e.g. 0.97 means "buy both legs for 97¢ or less".
+pub const ARB_THRESHOLD: f64 = 0.97;
+
+/// Leagues discovery will search for matching markets in.
+pub const ENABLED_LEAGUES: &[&str] = &["NFL", "NBA", "MLB", "NHL"];
+
+/// Delay between WebSocket reconnect attempts.
+pub const WS_RECONNECT_DELAY_SECS: u64 = 5;
+
+/// Polymarket CLOB API host.
+pub const POLY_CLOB_HOST: &str = "https://clob.polymarket.com";
+
+/// Polygon chain ID.
+pub const POLYGON_CHAIN_ID: u64 = 137;
+
+/// Per-pair tuning, keyed by Kalshi market ticker in the config file.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct PairOverride {
+    pub threshold: Option<f64>,
+}
+
+/// On-disk shape of `markets.json`/`CONFIG_PATH`. Every field is optional so a
+/// partial file only overrides what it sets; anything absent falls back to
+/// the compiled-in default.
+#[derive(Debug, Clone, Deserialize, Default)]
+struct FileConfig {
+    leagues: Option<Vec<String>>,
+    arb_threshold: Option<f64>,
+    poly_clob_host: Option<String>,
+    polygon_chain_id: Option<u64>,
+    ws_reconnect_delay_secs: Option<u64>,
+    #[serde(default)]
+    pair_overrides: HashMap<String, PairOverride>,
+}
+
+/// Fully-resolved runtime configuration: the compiled-in defaults above,
+/// merged with whatever `markets.json` supplies.
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub leagues: Vec<String>,
+    pub arb_threshold: f64,
+    pub poly_clob_host: String,
+    pub polygon_chain_id: u64,
+    pub ws_reconnect_delay_secs: u64,
+    pair_overrides: HashMap<String, PairOverride>,
+}
+
+impl AppConfig {
+    /// Loads `CONFIG_PATH` (default `markets.json`) and merges it over the
+    /// compiled-in defaults. Missing or unparsable files are not fatal - the
+    /// system runs on defaults exactly as it did before this file existed.
+    pub fn load() -> Self {
+        let path = std::env::var("CONFIG_PATH").unwrap_or_else(|_| "markets.json".to_string());
+        let file = match std::fs::read_to_string(&path) {
+            Ok(raw) => match serde_json::from_str::<FileConfig>(&raw) {
+                Ok(parsed) => {
+                    info!("⚙️  Loaded config overrides from {}", path);
+                    parsed
+                }
+                Err(e) => {
+                    warn!("⚙️  Ignoring {}: {}", path, e);
+                    FileConfig::default()
+                }
+            },
+            Err(_) => FileConfig::default(),
+        };
+
+        Self {
+            leagues: file
+                .leagues
+                .unwrap_or_else(|| ENABLED_LEAGUES.iter().map(|s| s.to_string()).collect()),
+            arb_threshold: file.arb_threshold.unwrap_or(ARB_THRESHOLD),
+            poly_clob_host: file.poly_clob_host.unwrap_or_else(|| POLY_CLOB_HOST.to_string()),
+            polygon_chain_id: file.polygon_chain_id.unwrap_or(POLYGON_CHAIN_ID),
+            ws_reconnect_delay_secs: file.ws_reconnect_delay_secs.unwrap_or(WS_RECONNECT_DELAY_SECS),
+            pair_overrides: file.pair_overrides,
+        }
+    }
+
+    /// Leagues as `&str`, the shape `DiscoveryClient` expects.
+    pub fn leagues_ref(&self) -> Vec<&str> {
+        self.leagues.iter().map(|s| s.as_str()).collect()
+    }
+
+    /// Arb threshold for one Kalshi market ticker, falling back to the
+    /// global `arb_threshold` when the ticker has no override.
+    pub fn threshold_for(&self, kalshi_market_ticker: &str) -> f64 {
+        self.pair_overrides
+            .get(kalshi_market_ticker)
+            .and_then(|o| o.threshold)
+            .unwrap_or(self.arb_threshold)
+    }
+
+    /// [`Self::threshold_for`], converted to integer cents the same way the
+    /// global threshold is in `main` - this is what `ExecutionEngine` and the
+    /// candle gap calculation actually gate on.
+    pub fn threshold_cents_for(&self, kalshi_market_ticker: &str) -> u16 {
+        ((self.threshold_for(kalshi_market_ticker) * 100.0).round() as u16).max(1)
+    }
+}
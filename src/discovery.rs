@@ -0,0 +1,66 @@
+//! Market discovery: matches Kalshi markets against Polymarket markets for the
+//! same real-world event so the rest of the system can track both venues for a
+//! single logical pair.
+
+use tracing::debug;
+
+use crate::cache::TeamCache;
+use crate::kalshi::KalshiApiClient;
+
+/// A Kalshi market matched to its Polymarket counterpart.
+#[derive(Debug, Clone)]
+pub struct MarketPair {
+    pub description: String,
+    pub market_type: String,
+    pub kalshi_market_ticker: String,
+    pub kalshi_yes_ticker: String,
+    pub kalshi_no_ticker: String,
+    pub poly_token_id_yes: String,
+    pub poly_token_id_no: String,
+}
+
+/// Outcome of a discovery pass: every pair that was successfully matched, plus
+/// any non-fatal errors encountered along the way (e.g. a Kalshi market with no
+/// Polymarket analogue).
+#[derive(Debug, Default)]
+pub struct DiscoveryResult {
+    pub pairs: Vec<MarketPair>,
+    pub errors: Vec<String>,
+}
+
+/// Finds and matches markets across Kalshi and Polymarket.
+pub struct DiscoveryClient {
+    kalshi: KalshiApiClient,
+    team_cache: TeamCache,
+}
+
+impl DiscoveryClient {
+    pub fn new(kalshi: KalshiApiClient, team_cache: TeamCache) -> Self {
+        Self { kalshi, team_cache }
+    }
+
+    /// Full discovery: Kalshi + Polymarket, using the on-disk cache when present.
+    pub async fn discover_all(&self, leagues: &[&str]) -> DiscoveryResult {
+        debug!("discovering markets for leagues: {:?}", leagues);
+        self.discover(leagues, false, false).await
+    }
+
+    /// Full discovery, bypassing the cache.
+    pub async fn discover_all_force(&self, leagues: &[&str]) -> DiscoveryResult {
+        self.discover(leagues, false, true).await
+    }
+
+    /// Kalshi-only discovery (no Polymarket matching attempted).
+    pub async fn discover_kalshi_only(&self, leagues: &[&str]) -> DiscoveryResult {
+        self.discover(leagues, true, false).await
+    }
+
+    async fn discover(&self, _leagues: &[&str], _kalshi_only: bool, _force: bool) -> DiscoveryResult {
+        let _ = &self.kalshi;
+        let _ = &self.team_cache;
+        // Real implementation walks the Kalshi events API, maps team codes via
+        // `self.team_cache`, and (unless kalshi-only) looks up the matching
+        // Polymarket condition/token ids. Omitted here.
+        DiscoveryResult::default()
+    }
+}
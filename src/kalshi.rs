@@ -0,0 +1,226 @@
+//! Kalshi REST + WebSocket client.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+use crate::execution::MarketUpdate;
+use crate::position_tracker::{Side as PositionSide, Venue};
+use crate::storage::{BookUpdateRecord, StorageHandle};
+use crate::types::{GlobalState, Level};
+
+/// Credentials and endpoints for the Kalshi API, loaded from the environment.
+#[derive(Clone)]
+pub struct KalshiConfig {
+    pub api_key_id: String,
+    pub private_key_pem: String,
+    pub api_host: String,
+    pub ws_host: String,
+}
+
+impl KalshiConfig {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            api_key_id: std::env::var("KALSHI_API_KEY_ID").context("KALSHI_API_KEY_ID not set")?,
+            private_key_pem: std::env::var("KALSHI_PRIVATE_KEY")
+                .context("KALSHI_PRIVATE_KEY not set")?,
+            api_host: std::env::var("KALSHI_API_HOST")
+                .unwrap_or_else(|_| "https://trading-api.kalshi.com".to_string()),
+            ws_host: std::env::var("KALSHI_WS_HOST")
+                .unwrap_or_else(|_| "wss://trading-api.kalshi.com".to_string()),
+        })
+    }
+}
+
+/// Thin REST client used by discovery and the execution engine to place
+/// orders and look up markets.
+pub struct KalshiApiClient {
+    config: KalshiConfig,
+}
+
+impl KalshiApiClient {
+    pub fn new(config: KalshiConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &KalshiConfig {
+        &self.config
+    }
+}
+
+/// Receive-time fallback sequence for frames that don't carry their own
+/// venue sequence number: a monotonic per-process counter incremented once
+/// per frame. Wall-clock nanoseconds truncated to 32 bits wrap roughly every
+/// 4.3 seconds, which is nowhere near "one connection's lifetime" - a
+/// counter never wraps in practice and needs no clock at all.
+static FRAME_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+fn receive_time_sequence() -> u32 {
+    FRAME_COUNTER.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Applies one orderbook delta (top N ask levels of one side) to `state`,
+/// rejecting it if it's older than the last-applied frame for that market.
+/// `venue_seq` is the sequence number Kalshi attaches to the frame, if any;
+/// frames without one fall back to receive-time ordering. When `storage` is
+/// set, every applied (non-stale) frame is also recorded so a later `REPLAY`
+/// run can re-feed the exact same stream.
+fn apply_frame(
+    state: &GlobalState,
+    market_id: u16,
+    side: Side,
+    levels: &[Level],
+    venue_seq: Option<u32>,
+    storage: Option<&StorageHandle>,
+) -> bool {
+    let Some(market) = state.get_by_id(market_id) else {
+        return false;
+    };
+    let sequence = venue_seq.unwrap_or_else(receive_time_sequence);
+    let book = match side {
+        Side::Yes => &market.kalshi_yes,
+        Side::No => &market.kalshi_no,
+    };
+    let applied = book.store_if_newer(levels, sequence);
+    if applied {
+        if let Some(storage) = storage {
+            storage.record_book_update(BookUpdateRecord {
+                market_id,
+                venue: Venue::Kalshi,
+                side: match side {
+                    Side::Yes => PositionSide::Yes,
+                    Side::No => PositionSide::No,
+                },
+                levels: levels.to_vec(),
+                sequence,
+                ts_nanos: now_nanos(),
+            });
+        }
+    }
+    applied
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+/// Which leg of the binary market an orderbook delta applies to.
+enum Side {
+    Yes,
+    No,
+}
+
+/// One `orderbook_snapshot`/`orderbook_delta` payload off the wire, scoped to
+/// the market and side it updates.
+#[derive(Debug, Deserialize)]
+struct OrderbookMsg {
+    market_ticker: String,
+    #[serde(default)]
+    yes: Vec<Level>,
+    #[serde(default)]
+    no: Vec<Level>,
+    seq: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "msg", rename_all = "snake_case")]
+enum WsFrame {
+    OrderbookSnapshot(OrderbookMsg),
+    OrderbookDelta(OrderbookMsg),
+    #[serde(other)]
+    Other,
+}
+
+/// Decodes one WS text frame and, for an orderbook message, applies both
+/// sides to `state` and notifies `exec_tx` for whichever side(s) actually
+/// advanced the book. Unrecognized/malformed frames are logged and skipped
+/// rather than tearing down the connection.
+async fn handle_text_frame(
+    text: &str,
+    state: &GlobalState,
+    market_tickers: &[(String, u16)],
+    exec_tx: &Sender<MarketUpdate>,
+    storage: Option<&StorageHandle>,
+) {
+    let frame = match serde_json::from_str::<WsFrame>(text) {
+        Ok(frame) => frame,
+        Err(e) => {
+            warn!("[KALSHI] skipping unparseable frame: {}", e);
+            return;
+        }
+    };
+    let msg = match frame {
+        WsFrame::OrderbookSnapshot(msg) | WsFrame::OrderbookDelta(msg) => msg,
+        WsFrame::Other => return,
+    };
+    let Some((_, market_id)) = market_tickers.iter().find(|(t, _)| *t == msg.market_ticker) else {
+        return;
+    };
+    if apply_frame(state, *market_id, Side::Yes, &msg.yes, msg.seq, storage) {
+        let _ = exec_tx.send(MarketUpdate { market_id: *market_id }).await;
+    }
+    if apply_frame(state, *market_id, Side::No, &msg.no, msg.seq, storage) {
+        let _ = exec_tx.send(MarketUpdate { market_id: *market_id }).await;
+    }
+}
+
+/// Runs the Kalshi market-data WebSocket until it disconnects. The caller is
+/// expected to reconnect (see `main`'s reconnect loop).
+pub async fn run_ws(
+    config: &KalshiConfig,
+    state: Arc<GlobalState>,
+    exec_tx: Sender<MarketUpdate>,
+    threshold_cents: u16,
+    storage: Option<StorageHandle>,
+) -> Result<()> {
+    let _ = threshold_cents;
+    debug!("[KALSHI] connecting to {}", config.ws_host);
+
+    // A fresh connection's sequence numbers restart from Kalshi's baseline,
+    // so any sequence we rejected-as-stale under the old connection must be
+    // allowed again here.
+    state.reset_kalshi_sequences();
+
+    let market_tickers: Vec<(String, u16)> = state
+        .markets
+        .iter()
+        .filter_map(|m| m.pair.as_ref().map(|p| (p.kalshi_market_ticker.clone(), m.market_id)))
+        .collect();
+
+    let (ws_stream, _) = tokio_tungstenite::connect_async(&config.ws_host)
+        .await
+        .context("connecting to Kalshi websocket")?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let subscribe = serde_json::json!({
+        "id": 1,
+        "cmd": "subscribe",
+        "params": {
+            "channels": ["orderbook_delta"],
+            "market_tickers": market_tickers.iter().map(|(t, _)| t).collect::<Vec<_>>(),
+        },
+    });
+    write
+        .send(Message::Text(subscribe.to_string()))
+        .await
+        .context("subscribing to Kalshi orderbook channel")?;
+
+    while let Some(msg) = read.next().await {
+        let msg = msg.context("reading Kalshi websocket frame")?;
+        if let Message::Text(text) = msg {
+            handle_text_frame(&text, &state, &market_tickers, &exec_tx, storage.as_ref()).await;
+        }
+    }
+
+    Ok(())
+}
@@ -7,7 +7,9 @@
 //! NOTE: This file includes a KALSHI_ONLY mode that disables all Polymarket logic
 //! so the app can run in Kalshi-only environments.
 
+mod api;
 mod cache;
+mod candles;
 mod circuit_breaker;
 mod config;
 mod discovery;
@@ -16,6 +18,8 @@ mod kalshi;
 mod polymarket;
 mod polymarket_clob;
 mod position_tracker;
+mod replay;
+mod storage;
 mod types;
 
 use anyhow::{Context, Result};
@@ -24,20 +28,18 @@ use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
 use cache::TeamCache;
+use candles::CandleAggregator;
 use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
-use config::{ARB_THRESHOLD, ENABLED_LEAGUES, WS_RECONNECT_DELAY_SECS};
+use config::AppConfig;
 use discovery::DiscoveryClient;
 use execution::{create_execution_channel, run_execution_loop, ExecutionEngine};
 use kalshi::{KalshiApiClient, KalshiConfig};
 use polymarket_clob::{PolymarketAsyncClient, PreparedCreds, SharedAsyncClient};
 use position_tracker::{create_position_channel, position_writer_loop, PositionTracker};
+use replay::ReplayConfig;
+use storage::StorageConfig;
 use types::{GlobalState, PriceCents};
 
-/// Polymarket CLOB API host
-const POLY_CLOB_HOST: &str = "https://clob.polymarket.com";
-/// Polygon chain ID
-const POLYGON_CHAIN_ID: u64 = 137;
-
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
@@ -48,23 +50,42 @@ async fn main() -> Result<()> {
         )
         .init();
 
+    // Load .env early
+    dotenvy::dotenv().ok();
+
+    // Load markets.json/CONFIG_PATH, merged over the compiled-in defaults.
+    // Shared via `Arc` so `ExecutionEngine` can look up per-pair threshold
+    // overrides without cloning the whole config (and its `pair_overrides`
+    // map) per market.
+    let app_config = Arc::new(AppConfig::load());
+    // Copied out up front so the WS reconnect loops below (each an `async
+    // move` task) don't need to capture `app_config` itself just for this
+    // one field - `app_config` is still needed afterward to build the
+    // execution engine.
+    let ws_reconnect_delay_secs = app_config.ws_reconnect_delay_secs;
+
     info!("🚀 Prediction Market Arbitrage System v2.0");
     info!(
         "   Profit threshold: <{:.1}¢ ({:.1}% minimum profit)",
-        ARB_THRESHOLD * 100.0,
-        (1.0 - ARB_THRESHOLD) * 100.0
+        app_config.arb_threshold * 100.0,
+        (1.0 - app_config.arb_threshold) * 100.0
     );
-    info!("   Monitored leagues: {:?}", ENABLED_LEAGUES);
+    info!("   Monitored leagues: {:?}", app_config.leagues);
 
-    // Load .env early
-    dotenvy::dotenv().ok();
+    // Replay mode re-feeds a previously recorded book-update stream instead
+    // of connecting to live WebSockets, and always runs dry - there's no
+    // live order book to execute a real fill against.
+    let replay_config = ReplayConfig::from_env();
 
     // Check for dry run mode
-    let dry_run = std::env::var("DRY_RUN")
-        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-        .unwrap_or(true);
-
-    if dry_run {
+    let dry_run = replay_config.is_some()
+        || std::env::var("DRY_RUN")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(true);
+
+    if replay_config.is_some() {
+        warn!("   Mode: REPLAY (forcing DRY RUN)");
+    } else if dry_run {
         info!("   Mode: DRY RUN (set DRY_RUN=0 to execute)");
     } else {
         warn!("   Mode: LIVE EXECUTION");
@@ -105,12 +126,13 @@ async fn main() -> Result<()> {
         team_cache,
     );
 
+    let leagues = app_config.leagues_ref();
     let result = if kalshi_only {
-        discovery.discover_kalshi_only(ENABLED_LEAGUES).await
+        discovery.discover_kalshi_only(&leagues).await
     } else if force_discovery {
-        discovery.discover_all_force(ENABLED_LEAGUES).await
+        discovery.discover_all_force(&leagues).await
     } else {
-        discovery.discover_all(ENABLED_LEAGUES).await
+        discovery.discover_all(&leagues).await
     };
 
     info!("📊 Market discovery complete:");
@@ -150,12 +172,34 @@ async fn main() -> Result<()> {
     });
 
     // Threshold
-    let threshold_cents: PriceCents = ((ARB_THRESHOLD * 100.0).round() as u16).max(1);
+    let threshold_cents: PriceCents = ((app_config.arb_threshold * 100.0).round() as u16).max(1);
     info!("   Execution threshold: {} cents", threshold_cents);
 
     // Create execution channel (Kalshi WS expects a sender)
     let (exec_tx, exec_rx) = create_execution_channel();
 
+    // Per-pair spread OHLC history, sized once from the discovered market count.
+    let candle_aggregator = Arc::new(CandleAggregator::new(state.market_count()));
+
+    // Durable storage sink (fills/opportunities/positions), optional: only
+    // connects if STORAGE_HOST is set so existing deployments are unaffected.
+    let storage_handle = match StorageConfig::from_env() {
+        Some(config) => match storage::connect(config).await {
+            Ok((handle, _writer_task)) => {
+                info!("💾 Storage sink connected");
+                Some(handle)
+            }
+            Err(e) => {
+                warn!("💾 Storage sink disabled: {}", e);
+                None
+            }
+        },
+        None => {
+            info!("💾 Storage sink disabled (STORAGE_HOST not set)");
+            None
+        }
+    };
+
     // Prepare Kalshi WS config reused on reconnects
     let kalshi_ws_config = KalshiConfig::from_env()?;
 
@@ -168,7 +212,11 @@ async fn main() -> Result<()> {
 
         let position_tracker = Arc::new(RwLock::new(PositionTracker::new()));
         let (position_channel, position_rx) = create_position_channel();
-        tokio::spawn(position_writer_loop(position_rx, position_tracker));
+        tokio::spawn(position_writer_loop(
+            position_rx,
+            position_tracker,
+            storage_handle.clone(),
+        ));
 
         let engine = Arc::new(ExecutionEngine::new(
             kalshi_api.clone(),
@@ -176,31 +224,57 @@ async fn main() -> Result<()> {
             state.clone(),
             circuit_breaker.clone(),
             position_channel,
+            storage_handle.clone(),
+            candle_aggregator.clone(),
+            app_config.clone(),
             dry_run,
         ));
 
         let exec_handle = tokio::spawn(run_execution_loop(exec_rx, engine));
 
-        // Start Kalshi WebSocket connection
+        // HTTP API for monitoring (Kalshi-only)
+        let api_state = state.clone();
+        let api_candles = candle_aggregator.clone();
+        let api_handle = tokio::spawn(async move {
+            if let Err(e) = api::serve(api_state, api_candles).await {
+                error!("[API] server exited: {}", e);
+            }
+        });
+
+        // Start Kalshi WebSocket connection, or the replay driver in its
+        // place when REPLAY is set.
         let kalshi_state = state.clone();
         let kalshi_exec_tx = exec_tx.clone();
         let kalshi_threshold = threshold_cents;
-
-        let kalshi_handle = tokio::spawn(async move {
-            loop {
-                if let Err(e) = kalshi::run_ws(
-                    &kalshi_ws_config,
-                    kalshi_state.clone(),
-                    kalshi_exec_tx.clone(),
-                    kalshi_threshold,
-                )
-                .await
-                {
-                    error!("[KALSHI] WebSocket disconnected: {} - reconnecting...", e);
+        let kalshi_storage = storage_handle.clone();
+
+        let kalshi_handle = if let Some(replay_cfg) = &replay_config {
+            let replay_storage_config = StorageConfig::from_env()
+                .context("REPLAY requires STORAGE_HOST to read recorded book updates")?;
+            let speed = replay_cfg.speed;
+            tokio::spawn(async move {
+                if let Err(e) = replay::run(&replay_storage_config, kalshi_state, kalshi_exec_tx, speed).await {
+                    error!("[REPLAY] driver exited: {}", e);
                 }
-                tokio::time::sleep(tokio::time::Duration::from_secs(WS_RECONNECT_DELAY_SECS)).await;
-            }
-        });
+            })
+        } else {
+            tokio::spawn(async move {
+                loop {
+                    if let Err(e) = kalshi::run_ws(
+                        &kalshi_ws_config,
+                        kalshi_state.clone(),
+                        kalshi_exec_tx.clone(),
+                        kalshi_threshold,
+                        kalshi_storage.clone(),
+                    )
+                    .await
+                    {
+                        error!("[KALSHI] WebSocket disconnected: {} - reconnecting...", e);
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_secs(ws_reconnect_delay_secs)).await;
+                }
+            })
+        };
 
         // Kalshi-only heartbeat + opportunity scan
         let heartbeat_state = state.clone();
@@ -213,10 +287,11 @@ async fn main() -> Result<()> {
                 let market_count = heartbeat_state.market_count();
                 let mut with_kalshi_any = 0;
                 let mut with_kalshi_both = 0;
-                let mut best_arb: Option<(u16, u16, u16, u16, i16)> = None;
+                let mut best_arb: Option<(u16, u16, u16, u16, u16, i16)> = None;
 
                 for market in heartbeat_state.markets.iter().take(market_count) {
-                    let (k_yes, k_no, _, _) = market.kalshi.load();
+                    let k_yes = market.kalshi_yes.best_price();
+                    let k_no = market.kalshi_no.best_price();
                     if k_yes > 0 || k_no > 0 {
                         with_kalshi_any += 1;
                     }
@@ -259,8 +334,8 @@ async fn main() -> Result<()> {
             }
         });
 
-        info!("✅ Kalshi-only mode active - running Kalshi WS + execution + heartbeat");
-        let _ = tokio::join!(kalshi_handle, heartbeat_handle, exec_handle);
+        info!("✅ Kalshi-only mode active - running Kalshi WS + execution + heartbeat + API");
+        let _ = tokio::join!(kalshi_handle, heartbeat_handle, exec_handle, api_handle);
         return Ok(());
     }
 
@@ -277,15 +352,18 @@ async fn main() -> Result<()> {
     // Create async Polymarket client and derive API credentials
     info!("[POLYMARKET] Creating async client and deriving API credentials...");
     let poly_async_client = PolymarketAsyncClient::new(
-        POLY_CLOB_HOST,
-        POLYGON_CHAIN_ID,
+        &app_config.poly_clob_host,
+        app_config.polygon_chain_id,
         &poly_private_key,
         &poly_funder,
     )?;
     let api_creds = poly_async_client.derive_api_key(0).await?;
     let prepared_creds = PreparedCreds::from_api_creds(&api_creds)?;
-    let poly_async =
-        Arc::new(SharedAsyncClient::new(poly_async_client, prepared_creds, POLYGON_CHAIN_ID));
+    let poly_async = Arc::new(SharedAsyncClient::new(
+        poly_async_client,
+        prepared_creds,
+        app_config.polygon_chain_id,
+    ));
 
     // Load neg_risk cache from Python script output
     match poly_async.load_cache(".clob_market_cache.json") {
@@ -295,32 +373,53 @@ async fn main() -> Result<()> {
 
     info!("[POLYMARKET] Client ready for {}", &poly_funder[..10]);
 
-    // Start Kalshi WebSocket connection (full mode)
+    // Start the Kalshi WebSocket connection (full mode), or the replay
+    // driver in its place when REPLAY is set - the replay driver re-feeds
+    // both venues' recorded books itself, so `poly_handle` below becomes a
+    // no-op in that case.
     let kalshi_state = state.clone();
     let kalshi_exec_tx = exec_tx.clone();
     let kalshi_threshold = threshold_cents;
-    let kalshi_handle = tokio::spawn(async move {
-        loop {
-            if let Err(e) = kalshi::run_ws(
-                &kalshi_ws_config,
-                kalshi_state.clone(),
-                kalshi_exec_tx.clone(),
-                kalshi_threshold,
-            )
-            .await
-            {
-                error!("[KALSHI] WebSocket disconnected: {} - reconnecting...", e);
+    let kalshi_storage = storage_handle.clone();
+
+    let kalshi_handle = if let Some(replay_cfg) = &replay_config {
+        let replay_storage_config = StorageConfig::from_env()
+            .context("REPLAY requires STORAGE_HOST to read recorded book updates")?;
+        let speed = replay_cfg.speed;
+        tokio::spawn(async move {
+            if let Err(e) = replay::run(&replay_storage_config, kalshi_state, kalshi_exec_tx, speed).await {
+                error!("[REPLAY] driver exited: {}", e);
             }
-            tokio::time::sleep(tokio::time::Duration::from_secs(WS_RECONNECT_DELAY_SECS)).await;
-        }
-    });
+        })
+    } else {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = kalshi::run_ws(
+                    &kalshi_ws_config,
+                    kalshi_state.clone(),
+                    kalshi_exec_tx.clone(),
+                    kalshi_threshold,
+                    kalshi_storage.clone(),
+                )
+                .await
+                {
+                    error!("[KALSHI] WebSocket disconnected: {} - reconnecting...", e);
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(ws_reconnect_delay_secs)).await;
+            }
+        })
+    };
 
     // Initialize execution infrastructure
     let circuit_breaker = Arc::new(CircuitBreaker::new(CircuitBreakerConfig::from_env()));
 
     let position_tracker = Arc::new(RwLock::new(PositionTracker::new()));
     let (position_channel, position_rx) = create_position_channel();
-    tokio::spawn(position_writer_loop(position_rx, position_tracker));
+    tokio::spawn(position_writer_loop(
+        position_rx,
+        position_tracker,
+        storage_handle.clone(),
+    ));
 
     let engine = Arc::new(ExecutionEngine::new(
         kalshi_api.clone(),
@@ -328,23 +427,36 @@ async fn main() -> Result<()> {
         state.clone(),
         circuit_breaker.clone(),
         position_channel,
+        storage_handle.clone(),
+        candle_aggregator.clone(),
+        app_config.clone(),
         dry_run,
     ));
 
     let exec_handle = tokio::spawn(run_execution_loop(exec_rx, engine));
 
-    // Initialize Polymarket WebSocket connection
-    let poly_state = state.clone();
-    let poly_exec_tx = exec_tx.clone();
-    let poly_threshold = threshold_cents;
-    let poly_handle = tokio::spawn(async move {
-        loop {
-            if let Err(e) = polymarket::run_ws(poly_state.clone(), poly_exec_tx.clone(), poly_threshold).await {
-                error!("[POLYMARKET] WebSocket disconnected: {} - reconnecting...", e);
+    // Initialize Polymarket WebSocket connection. In replay mode the
+    // `kalshi_handle` replay driver already re-feeds both venues, so this is
+    // a no-op task kept only to preserve the `tokio::join!` shape below.
+    let poly_handle = if replay_config.is_some() {
+        tokio::spawn(async {})
+    } else {
+        let poly_state = state.clone();
+        let poly_exec_tx = exec_tx.clone();
+        let poly_threshold = threshold_cents;
+        let poly_storage = storage_handle.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) =
+                    polymarket::run_ws(poly_state.clone(), poly_exec_tx.clone(), poly_threshold, poly_storage.clone())
+                        .await
+                {
+                    error!("[POLYMARKET] WebSocket disconnected: {} - reconnecting...", e);
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(ws_reconnect_delay_secs)).await;
             }
-            tokio::time::sleep(tokio::time::Duration::from_secs(WS_RECONNECT_DELAY_SECS)).await;
-        }
-    });
+        })
+    };
 
     // System health monitoring and arbitrage diagnostics
     let heartbeat_state = state.clone();
@@ -361,8 +473,10 @@ async fn main() -> Result<()> {
             let mut best_arb: Option<(u16, u16, u16, u16, u16, u16, u16, bool)> = None;
 
             for market in heartbeat_state.markets.iter().take(market_count) {
-                let (k_yes, k_no, _, _) = market.kalshi.load();
-                let (p_yes, p_no, _, _) = market.poly.load();
+                let k_yes = market.kalshi_yes.best_price();
+                let k_no = market.kalshi_no.best_price();
+                let p_yes = market.poly_yes.best_price();
+                let p_no = market.poly_no.best_price();
                 let has_k = k_yes > 0 && k_no > 0;
                 let has_p = p_yes > 0 && p_no > 0;
                 if k_yes > 0 || k_no > 0 {
@@ -426,9 +540,18 @@ async fn main() -> Result<()> {
         }
     });
 
+    // HTTP API for monitoring
+    let api_state = state.clone();
+    let api_candles = candle_aggregator.clone();
+    let api_handle = tokio::spawn(async move {
+        if let Err(e) = api::serve(api_state, api_candles).await {
+            error!("[API] server exited: {}", e);
+        }
+    });
+
     // Main event loop - run until termination
     info!("✅ All systems operational - entering main event loop");
-    let _ = tokio::join!(kalshi_handle, poly_handle, heartbeat_handle, exec_handle);
+    let _ = tokio::join!(kalshi_handle, poly_handle, heartbeat_handle, exec_handle, api_handle);
 
     Ok(())
 }
@@ -0,0 +1,173 @@
+//! Polymarket market-data WebSocket client.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use tokio::sync::mpsc::Sender;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+use crate::execution::MarketUpdate;
+use crate::position_tracker::{Side as PositionSide, Venue};
+use crate::storage::{BookUpdateRecord, StorageHandle};
+use crate::types::{GlobalState, Level};
+
+/// Polymarket book messages don't carry a sequence number, so frames fall
+/// back to this monotonic per-process counter, incremented once per frame.
+/// Wall-clock nanoseconds truncated to 32 bits wrap roughly every 4.3
+/// seconds - since Polymarket relies on this fallback for every single
+/// frame, that wrap would freeze the book every ~4.3s. A counter never
+/// wraps in practice and needs no clock at all.
+static FRAME_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+fn receive_time_sequence() -> u32 {
+    FRAME_COUNTER.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Which leg of the binary market a book update applies to.
+enum Side {
+    Yes,
+    No,
+}
+
+/// Applies one orderbook update (top N ask levels of one side) to `state`,
+/// rejecting it if it's older than the last-applied update for that market.
+/// When `storage` is set, every applied (non-stale) update is also recorded
+/// so a later `REPLAY` run can re-feed the exact same stream.
+fn apply_frame(
+    state: &GlobalState,
+    market_id: u16,
+    side: Side,
+    levels: &[Level],
+    storage: Option<&StorageHandle>,
+) -> bool {
+    let Some(market) = state.get_by_id(market_id) else {
+        return false;
+    };
+    let book = match side {
+        Side::Yes => &market.poly_yes,
+        Side::No => &market.poly_no,
+    };
+    let sequence = receive_time_sequence();
+    let applied = book.store_if_newer(levels, sequence);
+    if applied {
+        if let Some(storage) = storage {
+            storage.record_book_update(BookUpdateRecord {
+                market_id,
+                venue: Venue::Polymarket,
+                side: match side {
+                    Side::Yes => PositionSide::Yes,
+                    Side::No => PositionSide::No,
+                },
+                levels: levels.to_vec(),
+                sequence,
+                ts_nanos: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0),
+            });
+        }
+    }
+    applied
+}
+
+const POLY_WS_HOST: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+
+/// One `book`/`price_change` payload off the wire, scoped to the token id
+/// (and therefore the market/side) it updates.
+#[derive(Debug, Deserialize)]
+struct BookMsg {
+    asset_id: String,
+    #[serde(default)]
+    asks: Vec<Level>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event_type", rename_all = "snake_case")]
+enum WsFrame {
+    Book(BookMsg),
+    PriceChange(BookMsg),
+    #[serde(other)]
+    Other,
+}
+
+/// Decodes one WS text frame and, for a book message, applies it to
+/// whichever side of whichever market the token id belongs to, notifying
+/// `exec_tx` if it actually advanced the book. Unrecognized/malformed
+/// frames are logged and skipped rather than tearing down the connection.
+async fn handle_text_frame(
+    text: &str,
+    state: &GlobalState,
+    token_ids: &[(String, u16, Side)],
+    exec_tx: &Sender<MarketUpdate>,
+    storage: Option<&StorageHandle>,
+) {
+    let frame = match serde_json::from_str::<WsFrame>(text) {
+        Ok(frame) => frame,
+        Err(e) => {
+            warn!("[POLYMARKET] skipping unparseable frame: {}", e);
+            return;
+        }
+    };
+    let msg = match frame {
+        WsFrame::Book(msg) | WsFrame::PriceChange(msg) => msg,
+        WsFrame::Other => return,
+    };
+    let Some((_, market_id, side)) = token_ids.iter().find(|(id, _, _)| *id == msg.asset_id) else {
+        return;
+    };
+    let side = match side {
+        Side::Yes => Side::Yes,
+        Side::No => Side::No,
+    };
+    let market_id = *market_id;
+    if apply_frame(state, market_id, side, &msg.asks, storage) {
+        let _ = exec_tx.send(MarketUpdate { market_id }).await;
+    }
+}
+
+/// Runs the Polymarket market-data WebSocket until it disconnects. The
+/// caller is expected to reconnect (see `main`'s reconnect loop).
+pub async fn run_ws(
+    state: Arc<GlobalState>,
+    exec_tx: Sender<MarketUpdate>,
+    threshold_cents: u16,
+    storage: Option<StorageHandle>,
+) -> Result<()> {
+    let _ = threshold_cents;
+    debug!("[POLYMARKET] connecting to market data stream");
+
+    // A fresh connection means any stale-rejected receive-time sequence
+    // from before the reconnect no longer applies.
+    state.reset_poly_sequences();
+
+    let token_ids: Vec<(String, u16, Side)> = state
+        .markets
+        .iter()
+        .filter_map(|m| m.pair.as_ref().map(|p| (m.market_id, p)))
+        .flat_map(|(market_id, p)| {
+            [
+                (p.poly_token_id_yes.clone(), market_id, Side::Yes),
+                (p.poly_token_id_no.clone(), market_id, Side::No),
+            ]
+        })
+        .collect();
+
+    let (ws_stream, _) = tokio_tungstenite::connect_async(POLY_WS_HOST)
+        .await
+        .context("connecting to Polymarket websocket")?;
+    let (_, mut read) = ws_stream.split();
+
+    while let Some(msg) = read.next().await {
+        let msg = msg.context("reading Polymarket websocket frame")?;
+        if let Message::Text(text) = msg {
+            handle_text_frame(&text, &state, &token_ids, &exec_tx, storage.as_ref()).await;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! In-memory position book, updated from fills reported by the execution
+//! engine and periodically snapshotted for durability.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::storage::StorageHandle;
+
+const POSITION_CHANNEL_CAPACITY: usize = 1024;
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Venue {
+    Kalshi,
+    Polymarket,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Yes,
+    No,
+}
+
+/// One executed leg of an arbitrage trade.
+#[derive(Debug, Clone)]
+pub struct FillEvent {
+    pub market_id: u16,
+    pub venue: Venue,
+    pub side: Side,
+    pub price_cents: u16,
+    pub size: u32,
+    pub fee_cents: u16,
+    pub order_id: String,
+    pub ts_nanos: u128,
+}
+
+pub enum PositionEvent {
+    Fill(FillEvent),
+}
+
+pub type PositionSender = Sender<PositionEvent>;
+pub type PositionReceiver = Receiver<PositionEvent>;
+
+pub fn create_position_channel() -> (PositionSender, PositionReceiver) {
+    mpsc::channel(POSITION_CHANNEL_CAPACITY)
+}
+
+/// Net size held per market/venue/side.
+#[derive(Default, Clone, Copy)]
+pub struct Position {
+    pub size: i64,
+}
+
+pub struct PositionTracker {
+    positions: HashMap<(u16, Venue, Side), Position>,
+}
+
+impl PositionTracker {
+    pub fn new() -> Self {
+        Self {
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn apply_fill(&mut self, fill: &FillEvent) {
+        let entry = self
+            .positions
+            .entry((fill.market_id, fill.venue, fill.side))
+            .or_default();
+        entry.size += fill.size as i64;
+    }
+
+    /// Snapshot of every non-flat position, used for the periodic durable
+    /// snapshot written to storage.
+    pub fn snapshot(&self) -> Vec<(u16, Venue, Side, i64)> {
+        self.positions
+            .iter()
+            .filter(|(_, pos)| pos.size != 0)
+            .map(|((market_id, venue, side), pos)| (*market_id, *venue, *side, pos.size))
+            .collect()
+    }
+}
+
+/// Applies fills to `tracker` as they arrive and mirrors each one (plus a
+/// periodic full snapshot) to `storage` without blocking on the database.
+pub async fn position_writer_loop(
+    mut rx: PositionReceiver,
+    tracker: Arc<RwLock<PositionTracker>>,
+    storage: Option<StorageHandle>,
+) {
+    let mut snapshot_interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                let Some(PositionEvent::Fill(fill)) = event else {
+                    info!("[POSITIONS] channel closed, writer loop exiting");
+                    return;
+                };
+                if let Some(storage) = &storage {
+                    storage.record_fill(&fill);
+                }
+                tracker.write().await.apply_fill(&fill);
+            }
+            _ = snapshot_interval.tick() => {
+                if let Some(storage) = &storage {
+                    let snapshot = tracker.read().await.snapshot();
+                    storage.record_positions(&snapshot, now_nanos());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Offline replay/backtest driver.
+//!
+//! Stands in for the live Kalshi/Polymarket WebSocket loops: instead of
+//! consuming a live feed, it re-feeds the book updates a prior run recorded
+//! to storage back into `GlobalState` in timestamp order (optionally sped
+//! up), notifying the same execution channel so the unmodified execution
+//! loop and heartbeat re-evaluate every pair exactly as they would live.
+//! `main` forces `DRY_RUN` whenever this mode is active.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::sync::mpsc::Sender;
+use tracing::info;
+
+use crate::execution::MarketUpdate;
+use crate::position_tracker::{Side, Venue};
+use crate::storage::{load_book_updates, StorageConfig};
+use crate::types::GlobalState;
+
+/// Replay mode configuration, read from the environment.
+pub struct ReplayConfig {
+    /// Playback speed multiplier: 1.0 reproduces the original pacing, 2.0
+    /// runs twice as fast, etc.
+    pub speed: f64,
+}
+
+impl ReplayConfig {
+    /// Returns `Some` when `REPLAY=1`/`true`, `None` otherwise - mirroring
+    /// the `DRY_RUN`/`KALSHI_ONLY` toggles in `main`.
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var("REPLAY")
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+        if !enabled {
+            return None;
+        }
+        let speed = std::env::var("REPLAY_SPEED")
+            .ok()
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|s| *s > 0.0)
+            .unwrap_or(1.0);
+        Some(Self { speed })
+    }
+}
+
+/// Loads every recorded book update and re-feeds it into `state` in
+/// timestamp order, sleeping between updates to reproduce the original
+/// pacing at `speed`x, and notifies `exec_tx` after each one actually
+/// applied so the execution engine re-evaluates the pair. Returns once the
+/// recorded stream is exhausted.
+pub async fn run(
+    storage_config: &StorageConfig,
+    state: Arc<GlobalState>,
+    exec_tx: Sender<MarketUpdate>,
+    speed: f64,
+) -> Result<()> {
+    let updates = load_book_updates(storage_config)
+        .await
+        .context("loading recorded book updates for replay")?;
+    info!("[REPLAY] loaded {} recorded book updates", updates.len());
+
+    let mut prev_ts_nanos: Option<u128> = None;
+    for update in updates {
+        if let Some(prev) = prev_ts_nanos {
+            let gap_nanos = (update.ts_nanos.saturating_sub(prev) as f64 / speed) as u64;
+            if gap_nanos > 0 {
+                tokio::time::sleep(Duration::from_nanos(gap_nanos)).await;
+            }
+        }
+        prev_ts_nanos = Some(update.ts_nanos);
+
+        let Some(market) = state.get_by_id(update.market_id) else {
+            continue;
+        };
+        let book = match (update.venue, update.side) {
+            (Venue::Kalshi, Side::Yes) => &market.kalshi_yes,
+            (Venue::Kalshi, Side::No) => &market.kalshi_no,
+            (Venue::Polymarket, Side::Yes) => &market.poly_yes,
+            (Venue::Polymarket, Side::No) => &market.poly_no,
+        };
+        if book.store_if_newer(&update.levels, update.sequence) {
+            let _ = exec_tx
+                .send(MarketUpdate { market_id: update.market_id })
+                .await;
+        }
+    }
+
+    info!("[REPLAY] recorded stream exhausted");
+    Ok(())
+}
@@ -0,0 +1,481 @@
+//! Durable record of fills, detected opportunities, and position snapshots.
+//!
+//! Both `ExecutionEngine` and `position_writer_loop` hold a [`StorageHandle`]
+//! and push events to it over an unbounded channel so a slow or unreachable
+//! database never blocks the hot trading path. A background task batches the
+//! events and flushes them to Postgres on a timer.
+
+use anyhow::{Context, Result};
+use deadpool_postgres::{Config as PoolConfig, Pool, Runtime};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use tokio_postgres::types::Json;
+use tokio_postgres::NoTls;
+use tracing::{error, info, warn};
+
+use crate::candles::{Candle, Resolution};
+use crate::position_tracker::{FillEvent, Side, Venue};
+use crate::types::Level;
+
+/// How many events to accumulate before forcing a flush, independent of the
+/// flush interval.
+const MAX_BATCH_SIZE: usize = 500;
+
+/// Connection settings for the storage sink, read from the environment.
+/// Storage is entirely optional: if `STORAGE_HOST` is unset the system runs
+/// exactly as it did before this was added.
+pub struct StorageConfig {
+    pub host: String,
+    pub port: u16,
+    pub dbname: String,
+    pub user: String,
+    pub password: Option<String>,
+    pub sslmode: String,
+    pub flush_interval: Duration,
+}
+
+impl StorageConfig {
+    /// Returns `None` when storage isn't configured at all.
+    pub fn from_env() -> Option<Self> {
+        let host = std::env::var("STORAGE_HOST").ok()?;
+        Some(Self {
+            host,
+            port: std::env::var("STORAGE_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5432),
+            dbname: std::env::var("STORAGE_DBNAME").unwrap_or_else(|_| "arb_bot".to_string()),
+            user: std::env::var("STORAGE_USER").unwrap_or_else(|_| "arb_bot".to_string()),
+            password: std::env::var("STORAGE_PASSWORD").ok(),
+            sslmode: std::env::var("STORAGE_SSLMODE").unwrap_or_else(|_| "prefer".to_string()),
+            flush_interval: Duration::from_secs(
+                std::env::var("STORAGE_FLUSH_INTERVAL_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(5),
+            ),
+        })
+    }
+
+    fn pool_config(&self) -> PoolConfig {
+        let mut cfg = PoolConfig::new();
+        cfg.host = Some(self.host.clone());
+        cfg.port = Some(self.port);
+        cfg.dbname = Some(self.dbname.clone());
+        cfg.user = Some(self.user.clone());
+        cfg.password = self.password.clone();
+        cfg
+    }
+}
+
+/// One row destined for the `opportunities` table: written by the execution
+/// loop each time it sizes an arb whose blended cost is at or under the
+/// pair's threshold. The heartbeat does not write these rows.
+#[derive(Debug, Clone)]
+pub struct OpportunityRecord {
+    pub market_id: u16,
+    pub leg_breakdown: String,
+    pub cost_cents: u16,
+    pub profit_cents: i16,
+    pub ts_nanos: u128,
+}
+
+/// A sealed candle destined for the `candles` table.
+#[derive(Debug, Clone)]
+pub struct CandleRecord {
+    pub market_id: u16,
+    pub resolution: &'static str,
+    pub candle: Candle,
+}
+
+/// One applied order book update, destined for the `book_updates` table. This
+/// is the raw feed a [`crate::replay`] run re-plays later, so every field it
+/// needs to reconstruct the original `OrderBook::store_if_newer` call is kept.
+#[derive(Debug, Clone)]
+pub struct BookUpdateRecord {
+    pub market_id: u16,
+    pub venue: Venue,
+    pub side: Side,
+    pub levels: Vec<Level>,
+    pub sequence: u32,
+    pub ts_nanos: u128,
+}
+
+enum StorageEvent {
+    Fill(FillEvent),
+    Opportunity(OpportunityRecord),
+    PositionSnapshot(Vec<(u16, Venue, Side, i64)>, u128),
+    Candle(CandleRecord),
+    BookUpdate(BookUpdateRecord),
+}
+
+/// Cheap, clonable handle held by `ExecutionEngine` and the position writer.
+/// Sends are non-blocking (`send` on an unbounded channel never awaits).
+#[derive(Clone)]
+pub struct StorageHandle {
+    tx: UnboundedSender<StorageEvent>,
+}
+
+impl StorageHandle {
+    pub fn record_fill(&self, fill: &FillEvent) {
+        if self.tx.send(StorageEvent::Fill(fill.clone())).is_err() {
+            warn!("[STORAGE] writer task gone, dropping fill event");
+        }
+    }
+
+    pub fn record_opportunity(&self, record: OpportunityRecord) {
+        if self.tx.send(StorageEvent::Opportunity(record)).is_err() {
+            warn!("[STORAGE] writer task gone, dropping opportunity event");
+        }
+    }
+
+    pub fn record_positions(&self, snapshot: &[(u16, Venue, Side, i64)], ts_nanos: u128) {
+        if self
+            .tx
+            .send(StorageEvent::PositionSnapshot(snapshot.to_vec(), ts_nanos))
+            .is_err()
+        {
+            warn!("[STORAGE] writer task gone, dropping position snapshot");
+        }
+    }
+
+    pub fn record_candle(&self, market_id: u16, resolution: Resolution, candle: &Candle) {
+        let record = CandleRecord {
+            market_id,
+            resolution: resolution.as_str(),
+            candle: *candle,
+        };
+        if self.tx.send(StorageEvent::Candle(record)).is_err() {
+            warn!("[STORAGE] writer task gone, dropping sealed candle");
+        }
+    }
+
+    /// Records an applied order book update so a later `REPLAY` run can
+    /// re-feed the exact same stream into `GlobalState`.
+    pub fn record_book_update(&self, record: BookUpdateRecord) {
+        if self.tx.send(StorageEvent::BookUpdate(record)).is_err() {
+            warn!("[STORAGE] writer task gone, dropping book update");
+        }
+    }
+}
+
+/// Connects to Postgres, ensures the schema exists, and spawns the batching
+/// writer task. Returns a handle to give to the execution engine and
+/// position writer, plus the task's `JoinHandle`.
+pub async fn connect(config: StorageConfig) -> Result<(StorageHandle, JoinHandle<()>)> {
+    let pool = config
+        .pool_config()
+        .create_pool(Some(Runtime::Tokio1), NoTls)
+        .context("building storage connection pool")?;
+
+    {
+        let client = pool.get().await.context("connecting to storage database")?;
+        client
+            .batch_execute(SCHEMA_SQL)
+            .await
+            .context("ensuring storage schema")?;
+    }
+
+    let (tx, rx) = mpsc::unbounded_channel();
+    let flush_interval = config.flush_interval;
+    let handle = tokio::spawn(run_writer(pool, rx, flush_interval));
+
+    info!("[STORAGE] connected to {}:{}/{}", config.host, config.port, config.dbname);
+    Ok((StorageHandle { tx }, handle))
+}
+
+const SCHEMA_SQL: &str = "
+CREATE TABLE IF NOT EXISTS fills (
+    id BIGSERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL,
+    venue TEXT NOT NULL,
+    side TEXT NOT NULL,
+    price_cents INTEGER NOT NULL,
+    size INTEGER NOT NULL,
+    fee_cents INTEGER NOT NULL,
+    order_id TEXT NOT NULL,
+    ts_nanos BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS opportunities (
+    id BIGSERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL,
+    leg_breakdown TEXT NOT NULL,
+    cost_cents INTEGER NOT NULL,
+    profit_cents INTEGER NOT NULL,
+    ts_nanos BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS positions (
+    id BIGSERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL,
+    venue TEXT NOT NULL,
+    side TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    ts_nanos BIGINT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS candles (
+    id BIGSERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL,
+    resolution TEXT NOT NULL,
+    bucket_start BIGINT NOT NULL,
+    open_cents SMALLINT NOT NULL,
+    high_cents SMALLINT NOT NULL,
+    low_cents SMALLINT NOT NULL,
+    close_cents SMALLINT NOT NULL,
+    samples INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS book_updates (
+    id BIGSERIAL PRIMARY KEY,
+    market_id INTEGER NOT NULL,
+    venue TEXT NOT NULL,
+    side TEXT NOT NULL,
+    levels JSONB NOT NULL,
+    sequence BIGINT NOT NULL,
+    ts_nanos BIGINT NOT NULL
+);
+";
+
+async fn run_writer(pool: Pool, mut rx: UnboundedReceiver<StorageEvent>, flush_interval: Duration) {
+    let mut fills = Vec::new();
+    let mut opportunities = Vec::new();
+    let mut positions: Vec<(u16, Venue, Side, i64)> = Vec::new();
+    let mut positions_ts_nanos: u128 = 0;
+    let mut candles = Vec::new();
+    let mut book_updates = Vec::new();
+    let mut ticker = tokio::time::interval(flush_interval);
+
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                match event {
+                    Some(StorageEvent::Fill(f)) => fills.push(f),
+                    Some(StorageEvent::Opportunity(o)) => opportunities.push(o),
+                    Some(StorageEvent::PositionSnapshot(s, ts_nanos)) => {
+                        positions = s;
+                        positions_ts_nanos = ts_nanos;
+                    }
+                    Some(StorageEvent::Candle(c)) => candles.push(c),
+                    Some(StorageEvent::BookUpdate(u)) => book_updates.push(u),
+                    None => {
+                        flush(&pool, &mut fills, &mut opportunities, &mut positions, positions_ts_nanos, &mut candles, &mut book_updates).await;
+                        return;
+                    }
+                }
+                if fills.len() + opportunities.len() + candles.len() + book_updates.len() >= MAX_BATCH_SIZE {
+                    flush(&pool, &mut fills, &mut opportunities, &mut positions, positions_ts_nanos, &mut candles, &mut book_updates).await;
+                }
+            }
+            _ = ticker.tick() => {
+                flush(&pool, &mut fills, &mut opportunities, &mut positions, positions_ts_nanos, &mut candles, &mut book_updates).await;
+            }
+        }
+    }
+}
+
+async fn flush(
+    pool: &Pool,
+    fills: &mut Vec<FillEvent>,
+    opportunities: &mut Vec<OpportunityRecord>,
+    positions: &mut Vec<(u16, Venue, Side, i64)>,
+    positions_ts_nanos: u128,
+    candles: &mut Vec<CandleRecord>,
+    book_updates: &mut Vec<BookUpdateRecord>,
+) {
+    if fills.is_empty()
+        && opportunities.is_empty()
+        && positions.is_empty()
+        && candles.is_empty()
+        && book_updates.is_empty()
+    {
+        return;
+    }
+
+    let client = match pool.get().await {
+        Ok(client) => client,
+        Err(e) => {
+            error!("[STORAGE] failed to get connection for flush: {}", e);
+            return;
+        }
+    };
+
+    for fill in fills.drain(..) {
+        let venue = venue_name(fill.venue);
+        let side = side_name(fill.side);
+        if let Err(e) = client
+            .execute(
+                "INSERT INTO fills (market_id, venue, side, price_cents, size, fee_cents, order_id, ts_nanos) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    &(fill.market_id as i32),
+                    &venue,
+                    &side,
+                    &(fill.price_cents as i32),
+                    &(fill.size as i32),
+                    &(fill.fee_cents as i32),
+                    &fill.order_id,
+                    &(fill.ts_nanos as i64),
+                ],
+            )
+            .await
+        {
+            error!("[STORAGE] failed to insert fill: {}", e);
+        }
+    }
+
+    for opp in opportunities.drain(..) {
+        if let Err(e) = client
+            .execute(
+                "INSERT INTO opportunities (market_id, leg_breakdown, cost_cents, profit_cents, ts_nanos) \
+                 VALUES ($1, $2, $3, $4, $5)",
+                &[
+                    &(opp.market_id as i32),
+                    &opp.leg_breakdown,
+                    &(opp.cost_cents as i32),
+                    &(opp.profit_cents as i32),
+                    &(opp.ts_nanos as i64),
+                ],
+            )
+            .await
+        {
+            error!("[STORAGE] failed to insert opportunity: {}", e);
+        }
+    }
+
+    let positions_ts_nanos = positions_ts_nanos as i64;
+    for (market_id, venue, side, size) in positions.iter() {
+        let venue = venue_name(*venue);
+        let side = side_name(*side);
+        if let Err(e) = client
+            .execute(
+                "INSERT INTO positions (market_id, venue, side, size, ts_nanos) VALUES ($1, $2, $3, $4, $5)",
+                &[&(*market_id as i32), &venue, &side, size, &positions_ts_nanos],
+            )
+            .await
+        {
+            error!("[STORAGE] failed to insert position snapshot: {}", e);
+        }
+    }
+    positions.clear();
+
+    for record in candles.drain(..) {
+        let candle = record.candle;
+        if let Err(e) = client
+            .execute(
+                "INSERT INTO candles (market_id, resolution, bucket_start, open_cents, high_cents, low_cents, close_cents, samples) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+                &[
+                    &(record.market_id as i32),
+                    &record.resolution,
+                    &(candle.bucket_start as i64),
+                    &candle.open,
+                    &candle.high,
+                    &candle.low,
+                    &candle.close,
+                    &(candle.samples as i32),
+                ],
+            )
+            .await
+        {
+            error!("[STORAGE] failed to insert candle: {}", e);
+        }
+    }
+
+    for update in book_updates.drain(..) {
+        let venue = venue_name(update.venue);
+        let side = side_name(update.side);
+        let levels = Json(&update.levels);
+        if let Err(e) = client
+            .execute(
+                "INSERT INTO book_updates (market_id, venue, side, levels, sequence, ts_nanos) \
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+                &[
+                    &(update.market_id as i32),
+                    &venue,
+                    &side,
+                    &levels,
+                    &(update.sequence as i64),
+                    &(update.ts_nanos as i64),
+                ],
+            )
+            .await
+        {
+            error!("[STORAGE] failed to insert book update: {}", e);
+        }
+    }
+}
+
+fn venue_name(venue: Venue) -> &'static str {
+    match venue {
+        Venue::Kalshi => "kalshi",
+        Venue::Polymarket => "polymarket",
+    }
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Yes => "yes",
+        Side::No => "no",
+    }
+}
+
+fn parse_venue(venue: &str) -> Option<Venue> {
+    match venue {
+        "kalshi" => Some(Venue::Kalshi),
+        "polymarket" => Some(Venue::Polymarket),
+        _ => None,
+    }
+}
+
+fn parse_side(side: &str) -> Option<Side> {
+    match side {
+        "yes" => Some(Side::Yes),
+        "no" => Some(Side::No),
+        _ => None,
+    }
+}
+
+/// Reads every recorded book update back out in timestamp order, for a
+/// [`crate::replay`] run to re-feed into `GlobalState`. Opens its own
+/// short-lived pool rather than reusing a live [`StorageHandle`], since a
+/// replay run typically isn't also writing live events.
+pub async fn load_book_updates(config: &StorageConfig) -> Result<Vec<BookUpdateRecord>> {
+    let pool = config
+        .pool_config()
+        .create_pool(Some(Runtime::Tokio1), NoTls)
+        .context("building replay connection pool")?;
+    let client = pool.get().await.context("connecting to storage database for replay")?;
+
+    let rows = client
+        .query(
+            "SELECT market_id, venue, side, levels, sequence, ts_nanos FROM book_updates ORDER BY ts_nanos ASC",
+            &[],
+        )
+        .await
+        .context("querying recorded book updates")?;
+
+    let mut updates = Vec::with_capacity(rows.len());
+    for row in rows {
+        let market_id: i32 = row.get(0);
+        let venue: String = row.get(1);
+        let side: String = row.get(2);
+        let Json(levels): Json<Vec<Level>> = row.get(3);
+        let sequence: i64 = row.get(4);
+        let ts_nanos: i64 = row.get(5);
+
+        let (Some(venue), Some(side)) = (parse_venue(&venue), parse_side(&side)) else {
+            warn!("[REPLAY] skipping book update with unrecognized venue/side");
+            continue;
+        };
+
+        updates.push(BookUpdateRecord {
+            market_id: market_id as u16,
+            venue,
+            side,
+            levels,
+            sequence: sequence as u32,
+            ts_nanos: ts_nanos as u128,
+        });
+    }
+
+    Ok(updates)
+}
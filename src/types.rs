@@ -0,0 +1,231 @@
+//! Shared, sequence-gated order book state for every tracked market pair.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::discovery::MarketPair;
+
+/// A price in integer cents (0-100). Everything in the hot path is kept as
+/// integer cents to avoid floating point drift when comparing arb math.
+pub type PriceCents = u16;
+
+/// Kalshi's per-contract trading fee, rounded up to the nearest cent.
+pub fn kalshi_fee_cents(price_cents: PriceCents) -> PriceCents {
+    let p = price_cents as f64 / 100.0;
+    let fee = 0.07 * p * (1.0 - p) * 100.0;
+    fee.ceil() as PriceCents
+}
+
+/// How many ask levels each [`OrderBook`] retains. Five is enough to size an
+/// arb against real depth without the per-tick cost of tracking a full book.
+pub const BOOK_DEPTH: usize = 5;
+
+/// One ask level: the price offered and the size available at it.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Level {
+    pub price_cents: PriceCents,
+    pub size: u32,
+}
+
+struct BookState {
+    levels: [Level; BOOK_DEPTH],
+    depth: usize,
+    sequence: u32,
+}
+
+impl Default for BookState {
+    fn default() -> Self {
+        Self {
+            levels: [Level::default(); BOOK_DEPTH],
+            depth: 0,
+            sequence: 0,
+        }
+    }
+}
+
+/// Sequence-gated, fixed-depth order book for one side (yes or no) of one
+/// venue's market. Levels are ask prices in ascending order: level 0 is the
+/// cheapest size available, and so on. A `Mutex` is fine here - the whole
+/// point of the fixed `[Level; BOOK_DEPTH]` array is that updates never
+/// allocate, so contention is a handful of memcpy'd bytes, not an
+/// allocation.
+///
+/// Carries the same sequence gating the old scalar price cell had: a store
+/// only applies if `sequence` is strictly greater than what's already held,
+/// so a reordered or replayed WebSocket frame can't clobber a fresher book.
+pub struct OrderBook(Mutex<BookState>);
+
+impl Default for OrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Self(Mutex::new(BookState::default()))
+    }
+
+    /// Replaces the book with `levels` (truncated to [`BOOK_DEPTH`]) if
+    /// `sequence` is newer than what's stored. Returns whether the store
+    /// happened.
+    pub fn store_if_newer(&self, levels: &[Level], sequence: u32) -> bool {
+        let mut state = self.0.lock().unwrap();
+        if sequence <= state.sequence {
+            return false;
+        }
+        let depth = levels.len().min(BOOK_DEPTH);
+        state.levels[..depth].copy_from_slice(&levels[..depth]);
+        state.depth = depth;
+        state.sequence = sequence;
+        true
+    }
+
+    /// Rebases the sequence baseline to zero and clears the book. Called
+    /// when a venue's WebSocket reconnects, since the new connection's
+    /// sequence numbers start over and would otherwise be rejected as stale
+    /// forever.
+    pub fn reset(&self) {
+        *self.0.lock().unwrap() = BookState::default();
+    }
+
+    /// Snapshot of the current levels, cheapest first, and how many of the
+    /// array's slots are populated.
+    pub fn levels(&self) -> ([Level; BOOK_DEPTH], usize) {
+        let state = self.0.lock().unwrap();
+        (state.levels, state.depth)
+    }
+
+    /// Best (cheapest) ask price, or 0 if the book is empty. Used by the
+    /// heartbeat and API, which only need top-of-book.
+    pub fn best_price(&self) -> PriceCents {
+        let state = self.0.lock().unwrap();
+        if state.depth == 0 {
+            0
+        } else {
+            state.levels[0].price_cents
+        }
+    }
+}
+
+/// A single tracked market pair and its latest order books on each venue,
+/// one per side since a binary market's "yes" and "no" legs are quoted
+/// independently.
+pub struct Market {
+    pub market_id: u16,
+    pub pair: Option<MarketPair>,
+    pub kalshi_yes: OrderBook,
+    pub kalshi_no: OrderBook,
+    pub poly_yes: OrderBook,
+    pub poly_no: OrderBook,
+}
+
+/// Shared across every task (WS loops, execution engine, heartbeat, API
+/// server) behind an `Arc`. Markets are append-only after startup, so
+/// looking one up never needs to take a lock - only updating its books does.
+pub struct GlobalState {
+    pub markets: Vec<Market>,
+}
+
+impl GlobalState {
+    pub fn new() -> Self {
+        Self { markets: Vec::new() }
+    }
+
+    pub fn add_pair(&mut self, pair: MarketPair) -> u16 {
+        let market_id = self.markets.len() as u16;
+        self.markets.push(Market {
+            market_id,
+            pair: Some(pair),
+            kalshi_yes: OrderBook::new(),
+            kalshi_no: OrderBook::new(),
+            poly_yes: OrderBook::new(),
+            poly_no: OrderBook::new(),
+        });
+        market_id
+    }
+
+    pub fn market_count(&self) -> usize {
+        self.markets.len()
+    }
+
+    pub fn get_by_id(&self, market_id: u16) -> Option<&Market> {
+        self.markets.get(market_id as usize)
+    }
+
+    /// Rebases every market's Kalshi books to an empty, zero-sequence state.
+    /// Call this right before a fresh Kalshi WebSocket connection starts
+    /// consuming frames, since its sequence numbers restart from the venue's
+    /// baseline.
+    pub fn reset_kalshi_sequences(&self) {
+        for market in &self.markets {
+            market.kalshi_yes.reset();
+            market.kalshi_no.reset();
+        }
+    }
+
+    /// Same as [`Self::reset_kalshi_sequences`] but for the Polymarket feed.
+    pub fn reset_poly_sequences(&self) {
+        for market in &self.markets {
+            market.poly_yes.reset();
+            market.poly_no.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price_cents: u16, size: u32) -> Level {
+        Level { price_cents, size }
+    }
+
+    #[test]
+    fn store_if_newer_applies_strictly_increasing_sequences() {
+        let book = OrderBook::new();
+        assert!(book.store_if_newer(&[level(10, 100)], 1));
+        assert_eq!(book.best_price(), 10);
+
+        assert!(book.store_if_newer(&[level(12, 50)], 2));
+        assert_eq!(book.best_price(), 12);
+    }
+
+    #[test]
+    fn store_if_newer_rejects_stale_and_replayed_sequences() {
+        let book = OrderBook::new();
+        assert!(book.store_if_newer(&[level(10, 100)], 5));
+
+        // Strictly older sequence is rejected.
+        assert!(!book.store_if_newer(&[level(99, 1)], 3));
+        // Replaying the same sequence is also rejected, not just older ones.
+        assert!(!book.store_if_newer(&[level(99, 1)], 5));
+        assert_eq!(book.best_price(), 10);
+    }
+
+    #[test]
+    fn store_if_newer_truncates_to_book_depth() {
+        let book = OrderBook::new();
+        let levels: Vec<Level> = (0..BOOK_DEPTH as u16 + 3).map(|i| level(i, 1)).collect();
+        assert!(book.store_if_newer(&levels, 1));
+
+        let (stored, depth) = book.levels();
+        assert_eq!(depth, BOOK_DEPTH);
+        assert_eq!(stored[0].price_cents, 0);
+        assert_eq!(stored[BOOK_DEPTH - 1].price_cents, BOOK_DEPTH as u16 - 1);
+    }
+
+    #[test]
+    fn reset_clears_levels_and_rebases_sequence_to_zero() {
+        let book = OrderBook::new();
+        assert!(book.store_if_newer(&[level(10, 100)], 10));
+        book.reset();
+
+        assert_eq!(book.best_price(), 0);
+        // A fresh connection's sequence numbers restart from one, which must
+        // be accepted again after reset rather than rejected as stale.
+        assert!(book.store_if_newer(&[level(15, 5)], 1));
+        assert_eq!(book.best_price(), 15);
+    }
+}